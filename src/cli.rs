@@ -0,0 +1,22 @@
+use clap::Parser;
+
+/// Inspect and control Win32 windows from the terminal.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Print the enumerated window list as JSON and exit, instead of launching the interactive viewer.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Include each window's children in the JSON output (only with --json).
+    #[arg(long)]
+    pub children: bool,
+
+    /// Restrict enumeration to a single process id (only with --json).
+    #[arg(long)]
+    pub pid: Option<u32>,
+
+    /// Only include windows whose class name or window text contains this substring (case-insensitive; only with --json).
+    #[arg(long)]
+    pub filter: Option<String>,
+}
@@ -0,0 +1,12 @@
+pub trait Clipboard {
+    fn set_text(&mut self, text: String) -> Result<(), String>;
+}
+
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, text: String) -> Result<(), String> {
+        let mut clipboard = arboard::Clipboard::new().map_err(|error| error.to_string())?;
+        clipboard.set_text(text).map_err(|error| error.to_string())
+    }
+}
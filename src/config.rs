@@ -0,0 +1,115 @@
+use std::fs;
+
+use serde::Deserialize;
+use tui::style::Color;
+use tui::widgets::BorderType;
+
+const CONFIG_PATH: &str = "child_windows_viewer.toml";
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub base: Color,
+    pub border: Color,
+    pub highlight: Color,
+    pub text: Color,
+    pub text_highlight: Color,
+    pub border_type: BorderType,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            base: Color::Magenta,
+            border: Color::Blue,
+            highlight: Color::DarkGray,
+            text: Color::White,
+            text_highlight: Color::Yellow,
+            border_type: BorderType::Plain,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawConfig {
+    color_scheme: Option<RawColorScheme>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawColorScheme {
+    base: Option<RawColor>,
+    border: Option<RawColor>,
+    highlight: Option<RawColor>,
+    text: Option<RawColor>,
+    text_highlight: Option<RawColor>,
+    border_width: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Rgb(u8, u8, u8),
+    Named(String),
+}
+
+impl RawColor {
+    fn into_color(self) -> Color {
+        match self {
+            RawColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+            RawColor::Named(name) => named_color(&name).unwrap_or(Color::White),
+        }
+    }
+}
+
+fn named_color(name: &str) -> Option<Color> {
+    Some(match name.to_lowercase().as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        _ => return None,
+    })
+}
+
+fn border_type(border_width: u8) -> BorderType {
+    match border_width {
+        0 | 1 => BorderType::Plain,
+        2 => BorderType::Thick,
+        _ => BorderType::Double,
+    }
+}
+
+pub fn load() -> Theme {
+    let Ok(contents) = fs::read_to_string(CONFIG_PATH) else {
+        return Theme::default();
+    };
+
+    let Ok(raw) = toml::from_str::<RawConfig>(&contents) else {
+        return Theme::default();
+    };
+
+    let default = Theme::default();
+    let Some(color_scheme) = raw.color_scheme else {
+        return default;
+    };
+
+    Theme {
+        base: color_scheme.base.map(RawColor::into_color).unwrap_or(default.base),
+        border: color_scheme.border.map(RawColor::into_color).unwrap_or(default.border),
+        highlight: color_scheme.highlight.map(RawColor::into_color).unwrap_or(default.highlight),
+        text: color_scheme.text.map(RawColor::into_color).unwrap_or(default.text),
+        text_highlight: color_scheme.text_highlight.map(RawColor::into_color).unwrap_or(default.text_highlight),
+        border_type: color_scheme.border_width.map(border_type).unwrap_or(default.border_type),
+    }
+}
@@ -1,107 +1,366 @@
+mod cli;
+mod clipboard;
+mod config;
+mod terminal;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::{io, mem};
 use std::ptr::addr_of_mut;
 use std::time::{Duration, Instant};
 
+use clap::Parser;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
-use crossterm::execute;
-use crossterm::terminal::{enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use serde::{Serialize, Serializer};
 use tui::{Frame, Terminal};
 use tui::backend::{Backend, CrosstermBackend};
 use tui::layout::{Constraint, Direction, Layout};
-use tui::style::{Color, Style};
+use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, List, ListItem, ListState};
-use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
-use windows::Win32::UI::WindowsAndMessaging::{EnumChildWindows, EnumThreadWindows, EnumWindows, GetClassNameW, GetWindowTextW, GetWindowThreadProcessId};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use windows::Win32::Foundation::{BOOL, CloseHandle, GetLastError, HWND, LPARAM, PWSTR, RECT, WPARAM};
+use windows::Win32::System::Threading::{
+    OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumChildWindows, EnumThreadWindows, EnumWindows, GetClassNameW, GetWindowRect,
+    GetWindowTextW, GetWindowThreadProcessId, PostMessageW, SetForegroundWindow, SetWindowPos,
+    ShowWindow, SWP_NOZORDER, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, WM_CLOSE,
+};
 
 fn main() -> Result<(), io::Error> {
-    enable_raw_mode()?;
+    let args = cli::Args::parse();
+
+    if args.json {
+        return run_cli(&args);
+    }
+
+    let theme = config::load();
+
+    let _guard = terminal::TerminalGuard::enter()?;
 
     let stdout = io::stdout();
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    run_app(&mut terminal, theme)?;
 
-    run_app(&mut terminal)?;
+    Ok(())
+}
 
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+fn run_cli(args: &cli::Args) -> io::Result<()> {
+    let windows = enum_windows_filtered(args.pid, args.filter.as_deref());
+
+    let dump = windows
+        .into_iter()
+        .map(|window| {
+            let children = args.children.then(|| enum_child_windows(&window));
+            WindowDump { window, children }
+        })
+        .collect::<Vec<_>>();
+
+    let json = serde_json::to_string_pretty(&dump).map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+    println!("{}", json);
 
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterMode {
+    Prefix,
+    Flex,
+}
+
+impl FilterMode {
+    fn toggled(self) -> Self {
+        match self {
+            FilterMode::Prefix => FilterMode::Flex,
+            FilterMode::Flex => FilterMode::Prefix,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            FilterMode::Prefix => "prefix",
+            FilterMode::Flex => "flex",
+        }
+    }
+}
+
+impl Default for FilterMode {
+    fn default() -> Self { FilterMode::Flex }
+}
+
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+
+struct WindowNode {
+    window: Window,
+    children: Vec<WindowNode>,
+}
+
+fn build_tree() -> Vec<WindowNode> {
+    enum_windows().into_iter().map(build_node).collect()
+}
+
+fn build_node(window: Window) -> WindowNode {
+    let children = enum_child_windows(&window).into_iter().map(build_node).collect();
+    WindowNode { window, children }
+}
+
+fn tree_contains(nodes: &[WindowNode], handle: isize) -> bool {
+    nodes.iter().any(|node| node.window.handle.0 == handle || tree_contains(&node.children, handle))
+}
+
+fn collect_pids(nodes: &[WindowNode], out: &mut HashSet<u32>) {
+    for node in nodes {
+        out.insert(node.window.process_id);
+        collect_pids(&node.children, out);
+    }
+}
+
+struct DisplayEntry {
+    window: Window,
+    depth: usize,
+    has_children: bool,
+    collapsed: bool,
+    class_positions: Vec<usize>,
+    text_positions: Vec<usize>,
+    process_name: Option<String>,
+}
+
+fn flatten_tree(nodes: &[WindowNode], collapsed: &HashSet<isize>, ignore_collapsed: bool, depth: usize, out: &mut Vec<DisplayEntry>) {
+    for node in nodes {
+        let is_collapsed = collapsed.contains(&node.window.handle.0);
+
+        out.push(DisplayEntry {
+            window: node.window.clone(),
+            depth,
+            has_children: !node.children.is_empty(),
+            collapsed: is_collapsed,
+            class_positions: Vec::new(),
+            text_positions: Vec::new(),
+            process_name: None,
+        });
+
+        if !node.children.is_empty() && (ignore_collapsed || !is_collapsed) {
+            flatten_tree(&node.children, collapsed, ignore_collapsed, depth + 1, out);
+        }
+    }
+}
+
 struct AppState {
-    windows: StatefulList<Window>,
-    children: StatefulList<Window>,
+    tree: Vec<WindowNode>,
+    collapsed: HashSet<isize>,
+    selected: Option<isize>,
+    filter: String,
+    filter_mode: FilterMode,
+    filtering: bool,
+    status: Option<(String, Instant)>,
+    theme: config::Theme,
+    process_names: HashMap<u32, Option<String>>,
+    group_by_process: bool,
+    clipboard: Box<dyn clipboard::Clipboard>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(theme: config::Theme) -> Self {
+        let tree = build_tree();
+        let selected = tree.first().map(|node| node.window.handle.0);
+
         Self {
-            windows: StatefulList::from(enum_windows()),
-            children: StatefulList::from(vec![]),
+            tree,
+            collapsed: HashSet::new(),
+            selected,
+            filter: String::new(),
+            filter_mode: FilterMode::default(),
+            filtering: false,
+            status: None,
+            theme,
+            process_names: HashMap::new(),
+            group_by_process: false,
+            clipboard: Box::new(clipboard::SystemClipboard),
         }
     }
-    fn select_children(&mut self) {
-        if let Some(selected) = self.windows.selected_item() {
-            self.children.update(enum_child_windows(selected));
+
+    fn refresh(&mut self) {
+        self.tree = build_tree();
+
+        let still_present = self.selected.map(|selected| tree_contains(&self.tree, selected)).unwrap_or(false);
+
+        if !still_present {
+            self.selected = self.tree.first().map(|node| node.window.handle.0);
         }
+
+        let live_pids = {
+            let mut pids = HashSet::new();
+            collect_pids(&self.tree, &mut pids);
+            pids
+        };
+
+        self.process_names.retain(|pid, _| live_pids.contains(pid));
     }
-}
 
-struct StatefulList<T> {
-    state: ListState,
-    vec: Vec<T>,
-}
+    fn set_status(&mut self, message: String) {
+        self.status = Some((message, Instant::now()));
+    }
 
-impl<T> StatefulList<T> {
-    fn update(&mut self, items: Vec<T>) {
-        if self.state.selected().map(|selected| selected > items.len()).unwrap_or_default() {
-            self.state.select(Some(items.len() - 1));
+    fn run_action(&mut self, action: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.set_status(format!("{} ok", action)),
+            Err(error) => self.set_status(format!("{} failed: {}", action, error)),
         }
-        self.vec = items;
+
+        self.refresh();
     }
-    fn next(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => if i >= self.vec.len() - 1 { 0 } else { i + 1 }
-            None => 0,
+
+    fn selected_window(&self) -> Option<Window> {
+        let selected = self.selected?;
+        find_window(&self.tree, selected)
+    }
+
+    fn display_entries(&mut self) -> Vec<DisplayEntry> {
+        let mut entries = if self.filter.is_empty() {
+            let mut out = Vec::new();
+            flatten_tree(&self.tree, &self.collapsed, false, 0, &mut out);
+            out
+        } else {
+            let mut all = Vec::new();
+            flatten_tree(&self.tree, &self.collapsed, true, 0, &mut all);
+
+            let mut matched = all
+                .into_iter()
+                .filter_map(|mut entry| {
+                    let class_match = match_field(&self.filter, &entry.window.class_name, self.filter_mode);
+                    let text_match = match_field(&self.filter, &entry.window.window_text, self.filter_mode);
+
+                    if class_match.is_none() && text_match.is_none() {
+                        return None;
+                    }
+
+                    let (class_score, class_positions) = class_match.unwrap_or_default();
+                    let (text_score, text_positions) = text_match.unwrap_or_default();
+
+                    entry.class_positions = class_positions;
+                    entry.text_positions = text_positions;
+
+                    Some((class_score.max(text_score), entry))
+                })
+                .collect::<Vec<_>>();
+
+            matched.sort_by(|a, b| b.0.cmp(&a.0));
+
+            matched.into_iter().map(|(_, entry)| entry).collect()
         };
 
-        self.state.select(Some(i));
+        for entry in &mut entries {
+            entry.process_name = self.process_name(entry.window.process_id);
+        }
+
+        entries
     }
 
-    fn previous(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => if i == 0 { self.vec.len() - 1 } else { i - 1 }
+    fn process_name(&mut self, pid: u32) -> Option<String> {
+        if let Some(cached) = self.process_names.get(&pid) {
+            return cached.clone();
+        }
+
+        let resolved = resolve_process_image(pid);
+        self.process_names.insert(pid, resolved.clone());
+        resolved
+    }
+
+    fn toggle_group_by_process(&mut self) {
+        self.group_by_process = !self.group_by_process;
+    }
+
+    fn copy(&mut self, label: &str, text: String) {
+        match self.clipboard.set_text(text) {
+            Ok(()) => self.set_status(format!("copied {} to clipboard", label)),
+            Err(error) => self.set_status(format!("copy failed: {}", error)),
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let entries = self.display_entries();
+
+        if entries.is_empty() {
+            self.selected = None;
+            return;
+        }
+
+        let pos = self.selected.and_then(|selected| entries.iter().position(|entry| entry.window.handle.0 == selected));
+        let len = entries.len() as isize;
+
+        let new_pos = match pos {
+            Some(pos) => (pos as isize + delta).rem_euclid(len) as usize,
             None => 0,
         };
 
-        self.state.select(Some(i));
+        self.selected = Some(entries[new_pos].window.handle.0);
+    }
+
+    fn expand_selected(&mut self) {
+        if let Some(selected) = self.selected {
+            self.collapsed.remove(&selected);
+        }
     }
-    fn selected(&self) -> Option<usize> { self.state.selected() }
-    fn selected_item(&self) -> Option<&T> { self.selected().map(|i| &self.vec[i]) }
-}
 
-impl<T> From<Vec<T>> for StatefulList<T> {
-    fn from(vec: Vec<T>) -> Self {
-        let mut state = ListState::default();
+    fn collapse_selected(&mut self) {
+        if let Some(selected) = self.selected {
+            self.collapsed.insert(selected);
+        }
+    }
 
-        if !vec.is_empty() {
-            state.select(Some(0));
+    fn toggle_selected(&mut self) {
+        if let Some(selected) = self.selected {
+            if self.collapsed.contains(&selected) {
+                self.collapsed.remove(&selected);
+            } else {
+                self.collapsed.insert(selected);
+            }
         }
+    }
 
-        Self {
-            state,
-            vec,
+    fn enter_filter(&mut self) {
+        self.filtering = true;
+    }
+
+    fn exit_filter(&mut self) {
+        self.filtering = false;
+    }
+
+    fn push_filter_char(&mut self, c: char) {
+        self.filter.push(c);
+    }
+
+    fn pop_filter_char(&mut self) {
+        self.filter.pop();
+    }
+
+    fn toggle_filter_mode(&mut self) {
+        self.filter_mode = self.filter_mode.toggled();
+    }
+}
+
+fn find_window(nodes: &[WindowNode], handle: isize) -> Option<Window> {
+    for node in nodes {
+        if node.window.handle.0 == handle {
+            return Some(node.window.clone());
+        }
+
+        if let Some(found) = find_window(&node.children, handle) {
+            return Some(found);
         }
     }
+
+    None
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, theme: config::Theme) -> io::Result<()> {
     const RATE: Duration = Duration::from_millis(250);
+    const NUDGE_STEP: i32 = 10;
 
-    let mut app_state = AppState::new();
+    let mut app_state = AppState::new(theme);
     let mut last_tick = Instant::now();
 
     loop {
@@ -115,29 +374,121 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
             if let Event::Key(key) = crossterm::event::read()? {
                 use KeyCode::*;
 
-                match (key.modifiers, key.code) {
-                    (KeyModifiers::CONTROL, Char('c')) => break,
-                    (_, Char('q')) => break,
-                    (_, Up) => {
-                        app_state.windows.previous();
-                        app_state.select_children();
-                    },
-                    (_, Down) => {
-                        app_state.windows.next();
-                        app_state.select_children();
-                    },
-                    (_, Char('r')) => {
-                        app_state.windows.update(enum_windows());
-                        app_state.select_children();
-                    },
-                    _ => (),
+                if app_state.filtering {
+                    match (key.modifiers, key.code) {
+                        (KeyModifiers::CONTROL, Char('c')) => break,
+                        (_, Esc) | (_, Enter) => app_state.exit_filter(),
+                        (_, Tab) => app_state.toggle_filter_mode(),
+                        (_, Backspace) => app_state.pop_filter_char(),
+                        (_, Up) => app_state.move_selection(-1),
+                        (_, Down) => app_state.move_selection(1),
+                        (_, Char(c)) => app_state.push_filter_char(c),
+                        _ => (),
+                    }
+                } else {
+                    match (key.modifiers, key.code) {
+                        (KeyModifiers::CONTROL, Char('c')) => break,
+                        (_, Char('q')) => break,
+                        (_, Char('/')) => app_state.enter_filter(),
+                        (KeyModifiers::SHIFT, Left) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(-NUDGE_STEP, 0, 0, 0);
+                                app_state.run_action("move", result);
+                            }
+                        },
+                        (KeyModifiers::SHIFT, Right) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(NUDGE_STEP, 0, 0, 0);
+                                app_state.run_action("move", result);
+                            }
+                        },
+                        (KeyModifiers::SHIFT, Up) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, -NUDGE_STEP, 0, 0);
+                                app_state.run_action("move", result);
+                            }
+                        },
+                        (KeyModifiers::SHIFT, Down) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, NUDGE_STEP, 0, 0);
+                                app_state.run_action("move", result);
+                            }
+                        },
+                        (KeyModifiers::CONTROL, Left) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, 0, -NUDGE_STEP, 0);
+                                app_state.run_action("resize", result);
+                            }
+                        },
+                        (KeyModifiers::CONTROL, Right) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, 0, NUDGE_STEP, 0);
+                                app_state.run_action("resize", result);
+                            }
+                        },
+                        (KeyModifiers::CONTROL, Up) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, 0, 0, -NUDGE_STEP);
+                                app_state.run_action("resize", result);
+                            }
+                        },
+                        (KeyModifiers::CONTROL, Down) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.nudge(0, 0, 0, NUDGE_STEP);
+                                app_state.run_action("resize", result);
+                            }
+                        },
+                        (_, Up) => app_state.move_selection(-1),
+                        (_, Down) => app_state.move_selection(1),
+                        (_, Left) => app_state.collapse_selected(),
+                        (_, Right) => app_state.expand_selected(),
+                        (_, Enter) | (_, Char(' ')) => app_state.toggle_selected(),
+                        (_, Char('r')) => app_state.refresh(),
+                        (_, Char('p')) => app_state.toggle_group_by_process(),
+                        (_, Char('f')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.focus();
+                                app_state.run_action("focus", result);
+                            }
+                        },
+                        (_, Char('n')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.minimize();
+                                app_state.run_action("minimize", result);
+                            }
+                        },
+                        (_, Char('M')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.maximize();
+                                app_state.run_action("maximize", result);
+                            }
+                        },
+                        (_, Char('x')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let result = window.close();
+                                app_state.run_action("close", result);
+                            }
+                        },
+                        (_, Char('y')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                let text = format!("{:#x} {} \"{}\" pid={}", window.handle.0, window.class_name, window.window_text, window.process_id);
+                                app_state.copy("window details", text);
+                            }
+                        },
+                        (_, Char('Y')) => {
+                            if let Some(window) = app_state.selected_window() {
+                                app_state.copy("handle", format!("{:#x}", window.handle.0));
+                            }
+                        },
+                        _ => (),
+                    }
                 }
             }
         }
 
         if last_tick.elapsed() >= RATE {
             last_tick = Instant::now();
-            app_state.select_children();
+            app_state.refresh();
         }
     }
 
@@ -145,59 +496,285 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
 }
 
 fn ui<B: Backend>(f: &mut Frame<B>, app_state: &mut AppState) {
-    let layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+    if app_state.status.as_ref().map(|(_, set_at)| set_at.elapsed() >= STATUS_DURATION).unwrap_or_default() {
+        app_state.status = None;
+    }
+
+    let root = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)].as_ref())
         .split(f.size());
 
-    let main_windows = create_window_list(&app_state.windows,Block::default()
-        .title("Main Windows")
-        .style(Style::default().fg(Color::Blue))
-        .borders(Borders::ALL)
-    );
+    let entries = app_state.display_entries();
+    let selected_pos = app_state.selected
+        .and_then(|selected| entries.iter().position(|entry| entry.window.handle.0 == selected));
+
+    let list = create_window_list(&entries, selected_pos, themed_block("Windows", &app_state.theme), &app_state.theme, app_state.group_by_process);
+
+    let mut state = ListState::default();
+    state.select(selected_pos);
+
+    f.render_stateful_widget(list, root[0], &mut state);
+
+    let filter_bar = if app_state.filtering {
+        format!("/{} [{}]", app_state.filter, app_state.filter_mode.label())
+    } else if !app_state.filter.is_empty() {
+        format!("filter: {} [{}] (press / to edit)", app_state.filter, app_state.filter_mode.label())
+    } else {
+        "press / to filter, left/right or enter to collapse/expand".to_string()
+    };
+
+    f.render_widget(Paragraph::new(filter_bar), root[1]);
 
-    let child_windows = create_window_list(&app_state.children, Block::default()
-        .title("Children of Selected")
-        .style(Style::default().fg(Color::Red))
+    let status = app_state.status.as_ref().map(|(message, _)| message.as_str()).unwrap_or("");
+    f.render_widget(Paragraph::new(status).style(Style::default().fg(app_state.theme.text_highlight)), root[2]);
+}
+
+fn themed_block(title: &'static str, theme: &config::Theme) -> Block<'static> {
+    Block::default()
+        .title(title)
+        .style(Style::default().fg(theme.border))
         .borders(Borders::ALL)
-    );
+        .border_type(theme.border_type)
+}
+
+fn tree_prefix(entry: &DisplayEntry) -> String {
+    let indent = "  ".repeat(entry.depth);
+
+    let marker = if entry.has_children {
+        if entry.collapsed { "+ " } else { "- " }
+    } else {
+        "  "
+    };
+
+    format!("{}{}", indent, marker)
+}
 
-    f.render_stateful_widget(main_windows, layout[0], &mut app_state.windows.state);
-    f.render_stateful_widget(child_windows, layout[1], &mut app_state.children.state);
+const PROCESS_PALETTE: [Color; 6] = [Color::Cyan, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::LightRed];
+
+fn process_color(pid: u32) -> Color {
+    PROCESS_PALETTE[pid as usize % PROCESS_PALETTE.len()]
 }
 
-fn create_window_list<'a, 'b>(windows: &'a StatefulList<Window>, block: Block<'b>) -> List<'b> {
-    let mut items = windows
-        .vec
+fn create_window_list<'a>(entries: &[DisplayEntry], selected_pos: Option<usize>, block: Block<'a>, theme: &config::Theme, group_by_process: bool) -> List<'a> {
+    let mut items = entries
         .iter()
-        .map(|window| {
-            let spans = Spans::from(vec![
-                Span::styled(window.class_name.clone(), Style::default().fg(Color::Magenta)),
-                Span::raw("->"),
-                Span::from(window.window_text.clone()),
-            ]);
-            ListItem::new(spans)
+        .map(|entry| {
+            let mut spans = vec![Span::raw(tree_prefix(entry))];
+            spans.extend(highlighted_spans(&entry.window.class_name, &entry.class_positions, Style::default().fg(theme.base), theme.text_highlight));
+            spans.push(Span::raw("->"));
+            spans.extend(highlighted_spans(&entry.window.window_text, &entry.text_positions, Style::default(), theme.text_highlight));
+
+            if let Some(process_name) = &entry.process_name {
+                let file_name = Path::new(process_name).file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| process_name.clone());
+
+                let style = if group_by_process {
+                    Style::default().fg(process_color(entry.window.process_id))
+                } else {
+                    Style::default().fg(theme.text)
+                };
+
+                spans.push(Span::raw(" ["));
+                spans.push(Span::styled(file_name, style));
+                spans.push(Span::raw("]"));
+            }
+
+            ListItem::new(Spans::from(spans))
         })
         .collect::<Vec<ListItem>>();
 
-    if let Some(selected) = windows.selected() {
+    if let Some(selected) = selected_pos {
         let selected = &mut items[selected];
-        *selected = selected.clone().style(Style::default().bg(Color::DarkGray));
+        *selected = selected.clone().style(Style::default().bg(theme.highlight));
     }
 
     List::new(items)
         .block(block)
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(theme.text))
+}
+
+fn highlighted_spans(text: &str, positions: &[usize], base: Style, highlight_color: tui::style::Color) -> Vec<Span<'static>> {
+    let highlight = base.fg(highlight_color).add_modifier(Modifier::BOLD);
+
+    if positions.is_empty() {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut current_highlighted = false;
+
+    for (i, c) in text.chars().enumerate() {
+        let is_match = positions.contains(&i);
+
+        if i == 0 {
+            current_highlighted = is_match;
+        } else if is_match != current_highlighted {
+            spans.push(Span::styled(mem::take(&mut current), if current_highlighted { highlight } else { base }));
+            current_highlighted = is_match;
+        }
+
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        spans.push(Span::styled(current, if current_highlighted { highlight } else { base }));
+    }
+
+    spans
+}
+
+fn match_field(query: &str, field: &str, mode: FilterMode) -> Option<(i32, Vec<usize>)> {
+    match mode {
+        FilterMode::Prefix => prefix_match(query, field),
+        FilterMode::Flex => flex_match(query, field),
+    }
+}
+
+fn chars_match_ignore_case(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn prefix_match(query: &str, field: &str) -> Option<(i32, Vec<usize>)> {
+    let query_chars = query.chars().collect::<Vec<_>>();
+    let field_chars = field.chars().collect::<Vec<_>>();
+
+    if query_chars.len() > field_chars.len() {
+        return None;
+    }
+
+    let matches = query_chars.iter().zip(field_chars.iter()).all(|(&q, &f)| chars_match_ignore_case(q, f));
+
+    if matches {
+        Some((1000, (0..query_chars.len()).collect()))
+    } else {
+        None
+    }
 }
 
-#[derive(Debug, Clone)]
+fn flex_match(query: &str, field: &str) -> Option<(i32, Vec<usize>)> {
+    let field_chars = field.chars().collect::<Vec<_>>();
+
+    let mut query_chars = query.chars();
+    let mut next_query_char = query_chars.next();
+    let mut positions = Vec::new();
+    let mut score = 0i32;
+    let mut previous_match: Option<usize> = None;
+
+    for (i, &c) in field_chars.iter().enumerate() {
+        let Some(query_char) = next_query_char else { break };
+
+        if chars_match_ignore_case(c, query_char) {
+            let mut bonus = 1;
+
+            if i > 0 && previous_match == Some(i - 1) {
+                bonus += 3;
+            }
+
+            if i == 0 || field_chars[i - 1] == ' ' {
+                bonus += 2;
+            }
+
+            score += bonus;
+            positions.push(i);
+            previous_match = Some(i);
+            next_query_char = query_chars.next();
+        }
+    }
+
+    if next_query_char.is_none() {
+        Some((score, positions))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct Window {
     class_name: String,
     window_text: String,
+    #[serde(serialize_with = "serialize_hwnd")]
     handle: HWND,
     process_id: u32,
 }
 
+fn serialize_hwnd<S: Serializer>(handle: &HWND, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(handle.0 as i64)
+}
+
+#[derive(Debug, Serialize)]
+struct WindowDump {
+    #[serde(flatten)]
+    window: Window,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<Window>>,
+}
+
+impl Window {
+    fn focus(&self) -> Result<(), String> {
+        unsafe {
+            ShowWindow(self.handle, SW_RESTORE);
+            win32_result(SetForegroundWindow(self.handle).as_bool())
+        }
+    }
+
+    fn minimize(&self) -> Result<(), String> {
+        unsafe { ShowWindow(self.handle, SW_MINIMIZE); }
+        Ok(())
+    }
+
+    fn maximize(&self) -> Result<(), String> {
+        unsafe { ShowWindow(self.handle, SW_MAXIMIZE); }
+        Ok(())
+    }
+
+    fn close(&self) -> Result<(), String> {
+        unsafe { win32_result(PostMessageW(self.handle, WM_CLOSE, WPARAM(0), LPARAM(0)).as_bool()) }
+    }
+
+    fn nudge(&self, dx: i32, dy: i32, dw: i32, dh: i32) -> Result<(), String> {
+        unsafe {
+            let mut rect = RECT::default();
+            win32_result(GetWindowRect(self.handle, &mut rect).as_bool())?;
+
+            let x = rect.left + dx;
+            let y = rect.top + dy;
+            let width = (rect.right - rect.left) + dw;
+            let height = (rect.bottom - rect.top) + dh;
+
+            win32_result(SetWindowPos(self.handle, HWND(0), x, y, width, height, SWP_NOZORDER).as_bool())
+        }
+    }
+}
+
+fn win32_result(success: bool) -> Result<(), String> {
+    if success {
+        Ok(())
+    } else {
+        unsafe { Err(format!("error {}", GetLastError().0)) }
+    }
+}
+
+fn resolve_process_image(pid: u32) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        let mut buffer = [0u16; 260];
+        let mut size = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut size).as_bool();
+
+        CloseHandle(handle);
+
+        if ok {
+            Some(String::from_utf16_lossy(&buffer[..size as usize]))
+        } else {
+            None
+        }
+    }
+}
+
 fn enum_windows() -> Vec<Window> {
     let mut windows: Vec<Window> = Vec::new();
     let pointer = addr_of_mut!(windows) as isize;
@@ -217,6 +794,18 @@ fn enum_windows() -> Vec<Window> {
         .collect()
 }
 
+fn enum_windows_filtered(pid: Option<u32>, filter: Option<&str>) -> Vec<Window> {
+    let filter = filter.map(|filter| filter.to_lowercase());
+
+    enum_windows()
+        .into_iter()
+        .filter(|window| pid.map(|pid| window.process_id == pid).unwrap_or(true))
+        .filter(|window| filter.as_ref().map(|filter| {
+            window.class_name.to_lowercase().contains(filter) || window.window_text.to_lowercase().contains(filter)
+        }).unwrap_or(true))
+        .collect()
+}
+
 fn enum_child_windows(parent: &Window) -> Vec<Window> {
     let mut windows: Vec<Window> = Vec::new();
     let pointer = addr_of_mut!(windows) as isize;
@@ -247,4 +836,110 @@ unsafe extern "system" fn enum_window(handle: HWND, windows_pointer: LPARAM) ->
     });
 
     true.into()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    struct MockClipboard {
+        calls: Rc<RefCell<Vec<String>>>,
+        should_fail: bool,
+    }
+
+    impl clipboard::Clipboard for MockClipboard {
+        fn set_text(&mut self, text: String) -> Result<(), String> {
+            if self.should_fail {
+                return Err("mock failure".to_string());
+            }
+
+            self.calls.borrow_mut().push(text);
+            Ok(())
+        }
+    }
+
+    fn test_state(clipboard: Box<dyn clipboard::Clipboard>) -> AppState {
+        AppState {
+            tree: Vec::new(),
+            collapsed: HashSet::new(),
+            selected: None,
+            filter: String::new(),
+            filter_mode: FilterMode::default(),
+            filtering: false,
+            status: None,
+            theme: config::Theme::default(),
+            process_names: HashMap::new(),
+            group_by_process: false,
+            clipboard,
+        }
+    }
+
+    #[test]
+    fn copy_calls_set_text_and_reports_success() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let clipboard = MockClipboard { calls: calls.clone(), should_fail: false };
+        let mut state = test_state(Box::new(clipboard));
+
+        state.copy("window", "hello".to_string());
+
+        assert_eq!(calls.borrow().as_slice(), ["hello".to_string()]);
+        assert_eq!(state.status.map(|(message, _)| message), Some("copied window to clipboard".to_string()));
+    }
+
+    #[test]
+    fn copy_reports_failure() {
+        let clipboard = MockClipboard { calls: Rc::new(RefCell::new(Vec::new())), should_fail: true };
+        let mut state = test_state(Box::new(clipboard));
+
+        state.copy("window", "hello".to_string());
+
+        assert_eq!(state.status.map(|(message, _)| message), Some("copy failed: mock failure".to_string()));
+    }
+
+    #[test]
+    fn flex_match_empty_field_has_no_match() {
+        assert_eq!(flex_match("a", ""), None);
+    }
+
+    #[test]
+    fn flex_match_single_char_query_at_position_zero() {
+        let (score, positions) = flex_match("n", "Notepad").unwrap();
+
+        assert_eq!(positions, vec![0]);
+        assert!(score > 0);
+    }
+
+    #[test]
+    fn flex_match_scores_consecutive_matches_higher_than_scattered() {
+        let (consecutive_score, _) = flex_match("no", "Notepad").unwrap();
+        let (scattered_score, _) = flex_match("nd", "Notepad").unwrap();
+
+        assert!(consecutive_score > scattered_score);
+    }
+
+    #[test]
+    fn flex_match_no_match_when_query_chars_are_out_of_order() {
+        assert_eq!(flex_match("pn", "Notepad"), None);
+    }
+
+    #[test]
+    fn prefix_match_empty_field_has_no_match() {
+        assert_eq!(prefix_match("a", ""), None);
+    }
+
+    #[test]
+    fn prefix_match_single_char_query_at_position_zero() {
+        let (score, positions) = prefix_match("n", "Notepad").unwrap();
+
+        assert_eq!(positions, vec![0]);
+        assert_eq!(score, 1000);
+    }
+
+    #[test]
+    fn prefix_match_no_match_when_query_is_not_a_prefix() {
+        assert_eq!(prefix_match("ted", "Notepad"), None);
+    }
+}